@@ -4,23 +4,28 @@ use std::{
     collections::HashMap,
     error::Error,
     fmt::Debug,
-    rc::Rc,
+    marker::PhantomData,
+    rc::{Rc, Weak},
 };
 
 use elsa::FrozenMap;
 
+pub mod sync;
+
 #[derive(Debug, thiserror::Error)]
 pub enum ResolveErrorKind {
     #[error("Service not found!")]
     NotFound,
-    #[error("Circular reference while resolving!")]
-    CircularReferenceFound,
+    #[error("Circular reference found: {0}!")]
+    CircularReferenceFound(String),
     #[error("Error while resolving service!")]
     ErrorWhileResolving(
         #[from]
         #[source]
         Box<dyn Error>,
     ),
+    #[error("The parent ServiceProvider has been dropped!")]
+    ParentDropped,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -42,15 +47,12 @@ impl ResolveError {
 pub unsafe trait Factory {
     fn type_name(&self) -> &'static str;
     fn type_id(&self) -> TypeId;
-    fn resolve(
-        self: Box<Self>,
-        service_provider: &ServiceProvider,
-    ) -> Result<Rc<dyn Any>, Box<dyn Error>>;
+    fn resolve(&self, service_provider: &ServiceProvider) -> Result<Rc<dyn Any>, Box<dyn Error>>;
 }
 
 unsafe impl<T: Any + 'static, F> Factory for F
 where
-    F: FnOnce(&ServiceProvider) -> Result<T, Box<dyn Error>>,
+    F: Fn(&ServiceProvider) -> Result<T, Box<dyn Error>>,
 {
     fn type_name(&self) -> &'static str {
         type_name::<T>()
@@ -60,25 +62,56 @@ where
         TypeId::of::<T>()
     }
 
-    fn resolve(
-        self: Box<Self>,
-        service_provider: &ServiceProvider,
-    ) -> Result<Rc<dyn Any>, Box<dyn Error>> {
+    fn resolve(&self, service_provider: &ServiceProvider) -> Result<Rc<dyn Any>, Box<dyn Error>> {
         let service = self(service_provider)?;
 
         Ok(Rc::new(service))
     }
 }
 
+/// How long a resolved instance is kept alive once it has been built.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceLifetime {
+    /// Built once per root [`ServiceProvider`] and reused for every later
+    /// `resolve`, including those made through its child scopes.
+    Singleton,
+    /// Re-run on every `resolve`; the result is never cached.
+    Transient,
+    /// Cached per scope; behaves like `Singleton` until the provider has
+    /// child scopes of its own.
+    Scoped,
+}
+
 impl Debug for dyn Factory {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_fmt(format_args!("Factory for {0}", self.type_name()))
     }
 }
 
+/// Registered factories grouped by the `TypeId` they resolve, together with
+/// the lifetime each was registered under.
+type FactoryMap = HashMap<TypeId, Vec<(ServiceLifetime, Box<dyn Factory>)>>;
+
 pub struct ServiceProvider {
-    factories: RefCell<HashMap<TypeId, Box<dyn Factory>>>,
-    instances: FrozenMap<TypeId, Box<Rc<dyn Any>>>,
+    factories: Rc<RefCell<FactoryMap>>,
+    instances: FrozenMap<(TypeId, usize), Box<Rc<dyn Any>>>,
+    resolving_ids: RefCell<Vec<TypeId>>,
+    resolving_names: RefCell<Vec<&'static str>>,
+    parent: Option<Weak<ServiceProvider>>,
+}
+
+/// Keeps the resolution stack balanced by popping the entry pushed for the
+/// current `resolve` call, even when that call returns early via `?`.
+struct ResolutionGuard<'a> {
+    ids: &'a RefCell<Vec<TypeId>>,
+    names: &'a RefCell<Vec<&'static str>>,
+}
+
+impl<'a> Drop for ResolutionGuard<'a> {
+    fn drop(&mut self) {
+        self.ids.borrow_mut().pop();
+        self.names.borrow_mut().pop();
+    }
 }
 
 impl Debug for ServiceProvider {
@@ -90,50 +123,275 @@ impl Debug for ServiceProvider {
 }
 
 impl ServiceProvider {
+    /// Builds a provider where every factory is registered as a `Singleton`.
+    ///
+    /// Several factories for the same type are kept in iteration order, as
+    /// with [`ServiceCollection`]; prefer the latter when services need a
+    /// different lifetime.
     pub fn new<I: IntoIterator<Item = Box<dyn Factory>>>(factories: I) -> Self {
+        let mut grouped: FactoryMap = HashMap::new();
+
+        for factory in factories {
+            grouped
+                .entry(Factory::type_id(&*factory))
+                .or_default()
+                .push((ServiceLifetime::Singleton, factory));
+        }
+
+        Self::from_factories(grouped)
+    }
+
+    pub(crate) fn from_factories(factories: FactoryMap) -> Self {
         Self {
-            factories: RefCell::new(
-                factories
-                    .into_iter()
-                    .map(|f| {
-                        eprintln!("Factory: {0} - {1:?}", f.type_name(), f.type_id());
-                        (f.type_id(), f)
-                    })
-                    .collect(),
-            ),
+            factories: Rc::new(RefCell::new(factories)),
             instances: FrozenMap::new(),
+            resolving_ids: RefCell::new(Vec::new()),
+            resolving_names: RefCell::new(Vec::new()),
+            parent: None,
         }
     }
 
+    /// Creates a child scope that shares this provider's registrations but
+    /// has its own `Scoped`-lifetime instance cache, e.g.
+    /// `let scope = Rc::clone(&provider).create_scope();`.
+    ///
+    /// `Singleton` lookups are forwarded up to this provider (and, if this
+    /// provider is itself a scope, further up to the root) so a singleton is
+    /// only ever built once for the whole tree. The scope only holds a
+    /// [`Weak`] reference back, so dropping every other `Rc` to this
+    /// provider does not keep it alive just because a scope exists; resolving
+    /// a `Singleton` through the scope afterwards fails with `ParentDropped`,
+    /// while `Scoped` and `Transient` registrations still resolve in the scope.
+    pub fn create_scope(self: Rc<Self>) -> Rc<ServiceProvider> {
+        Rc::new(ServiceProvider {
+            factories: Rc::clone(&self.factories),
+            instances: FrozenMap::new(),
+            resolving_ids: RefCell::new(Vec::new()),
+            resolving_names: RefCell::new(Vec::new()),
+            parent: Some(Rc::downgrade(&self)),
+        })
+    }
+
+    /// Resolves the last service registered for `T`.
+    ///
+    /// See [`ServiceProvider::resolve_all`] to get every registration.
     pub fn resolve<T: 'static>(&self) -> Result<Rc<T>, ResolveError> {
-        eprintln!("Resolve {0} in {1:?}", type_name::<T>(), self);
+        self.resolve_all::<T>()?
+            .into_iter()
+            .last()
+            .ok_or(ResolveErrorKind::NotFound)
+            .map_err(ResolveError::for_type::<T>())
+    }
+
+    /// Resolves `T` like [`ServiceProvider::resolve`], but returns `None`
+    /// instead of an error when nothing is registered for `T`. Any
+    /// `NotFound` maps to `None`, including one raised for a missing
+    /// dependency of a registered `T`.
+    ///
+    /// Genuine construction failures (`ErrorWhileResolving`) and circular
+    /// references still panic, since those indicate a broken registration
+    /// rather than an absent optional dependency.
+    pub fn try_resolve<T: 'static>(&self) -> Option<Rc<T>> {
+        match self.resolve::<T>() {
+            Ok(service) => Some(service),
+            Err(ResolveError {
+                kind: ResolveErrorKind::NotFound,
+                ..
+            }) => None,
+            Err(error) => panic!("{error}"),
+        }
+    }
 
+    /// Resolves every service registered for `T`, in registration order.
+    pub fn resolve_all<T: 'static>(&self) -> Result<Vec<Rc<T>>, ResolveError> {
         let type_id = TypeId::of::<T>();
 
-        if let Some(any) = self.instances.get(&type_id).cloned() {
-            Ok(any
-                .downcast()
-                .expect("we resolved by TypeId so it should be a T"))
-        } else {
-            let factory = {
-                let mut factories = self.factories.borrow_mut();
-                factories
-                    .remove(&type_id)
-                    .ok_or(ResolveErrorKind::NotFound)
-                    .map_err(ResolveError::for_type::<T>())?
+        let count = self
+            .factories
+            .borrow()
+            .get(&type_id)
+            .map(Vec::len)
+            .ok_or(ResolveErrorKind::NotFound)
+            .map_err(ResolveError::for_type::<T>())?;
+
+        let mut services = Vec::with_capacity(count);
+
+        for index in 0..count {
+            let lifetime = self.factories.borrow()[&type_id][index].0;
+
+            let service = match lifetime {
+                ServiceLifetime::Singleton => self.resolve_singleton::<T>(index)?,
+                _ => self.resolve_index::<T>(index)?,
             };
 
-            let service = factory
-                .resolve(self)
-                .map_err(ResolveErrorKind::ErrorWhileResolving)
-                .map_err(ResolveError::for_type::<T>())?;
+            services.push(service);
+        }
+
+        Ok(services)
+    }
+
+    /// Resolves a `Singleton` registration by climbing to the root provider,
+    /// so nested scopes (`scope2` of `scope2 -> scope1 -> root`) still share
+    /// the one instance cached on `root` instead of each hop caching its own.
+    fn resolve_singleton<T: 'static>(&self, index: usize) -> Result<Rc<T>, ResolveError> {
+        match &self.parent {
+            Some(parent) => {
+                let parent = parent
+                    .upgrade()
+                    .ok_or(ResolveErrorKind::ParentDropped)
+                    .map_err(ResolveError::for_type::<T>())?;
+
+                parent.resolve_singleton::<T>(index)
+            }
+            None => self.resolve_index::<T>(index),
+        }
+    }
 
-            self.instances.insert(type_id, Box::new(service.clone()));
+    /// Resolves (and, depending on lifetime, caches) a single registration
+    /// for `T`, using `self` as both the instance cache and the context
+    /// passed to the factory for its own nested `resolve` calls.
+    fn resolve_index<T: 'static>(&self, index: usize) -> Result<Rc<T>, ResolveError> {
+        let type_id = TypeId::of::<T>();
+        let key = (type_id, index);
 
-            Ok(service
+        if let Some(any) = self.instances.get(&key).cloned() {
+            return Ok(any
                 .downcast()
-                .expect("We just resolved the factory for T"))
+                .expect("we resolved by TypeId so it should be a T"));
+        }
+
+        if self.resolving_ids.borrow().contains(&type_id) {
+            let mut chain = self.resolving_names.borrow().clone();
+            chain.push(type_name::<T>());
+
+            return Err(ResolveErrorKind::CircularReferenceFound(chain.join(" -> ")))
+                .map_err(ResolveError::for_type::<T>());
+        }
+
+        self.resolving_ids.borrow_mut().push(type_id);
+        self.resolving_names.borrow_mut().push(type_name::<T>());
+        let _guard = ResolutionGuard {
+            ids: &self.resolving_ids,
+            names: &self.resolving_names,
+        };
+
+        let (lifetime, service) = {
+            let factories = self.factories.borrow();
+            let (lifetime, factory) = &factories[&type_id][index];
+
+            let service = factory.resolve(self).map_err(|err| {
+                // A nested `resolve` call boxes its `ResolveError` like any
+                // other error; unwrap it instead of re-wrapping it in
+                // `ErrorWhileResolving` so callers still see the original
+                // `NotFound`/`CircularReferenceFound`/`ParentDropped`.
+                match err.downcast::<ResolveError>() {
+                    Ok(resolve_error) => *resolve_error,
+                    Err(err) => ResolveError::for_type::<T>()(
+                        ResolveErrorKind::ErrorWhileResolving(err),
+                    ),
+                }
+            })?;
+
+            (*lifetime, service)
+        };
+
+        if lifetime != ServiceLifetime::Transient {
+            self.instances.insert(key, Box::new(service.clone()));
         }
+
+        Ok(service
+            .downcast()
+            .expect("We just resolved the factory for T"))
+    }
+}
+
+/// Builds up the set of registered services before handing them to a
+/// [`ServiceProvider`] via [`ServiceCollection::build`].
+#[derive(Default)]
+pub struct ServiceCollection {
+    factories: FactoryMap,
+}
+
+impl ServiceCollection {
+    pub fn new() -> Self {
+        Self {
+            factories: HashMap::new(),
+        }
+    }
+
+    /// Registers `T` as a `Singleton`: built once and reused for every `resolve`.
+    ///
+    /// Registering `T` more than once adds an additional entry rather than
+    /// replacing the previous one; see [`ServiceProvider::resolve_all`].
+    pub fn add<T: Any + 'static>(&mut self) -> ServiceBuilder<'_, T> {
+        self.add_with_lifetime(ServiceLifetime::Singleton)
+    }
+
+    /// Registers `T` as `Transient`: rebuilt on every `resolve`.
+    pub fn add_transient<T: Any + 'static>(&mut self) -> ServiceBuilder<'_, T> {
+        self.add_with_lifetime(ServiceLifetime::Transient)
+    }
+
+    /// Registers `T` as `Scoped`: shared within a scope, rebuilt per scope.
+    pub fn add_scoped<T: Any + 'static>(&mut self) -> ServiceBuilder<'_, T> {
+        self.add_with_lifetime(ServiceLifetime::Scoped)
+    }
+
+    /// Registers an implementation against a trait-object interface, e.g.
+    /// `services.add_trait::<dyn Foo>().with_fallible_factory(|sp| Ok(Box::new(FooImpl::new(sp.resolve()?)) as Box<dyn Foo>))`.
+    /// Consumers then depend on `Box<dyn Foo>` instead of the concrete type,
+    /// resolving it with `sp.resolve::<Box<dyn Foo>>()`.
+    pub fn add_trait<Trait: ?Sized + 'static>(&mut self) -> ServiceBuilder<'_, Box<Trait>> {
+        self.add_with_lifetime(ServiceLifetime::Singleton)
+    }
+
+    fn add_with_lifetime<T: Any + 'static>(
+        &mut self,
+        lifetime: ServiceLifetime,
+    ) -> ServiceBuilder<'_, T> {
+        ServiceBuilder {
+            collection: self,
+            lifetime,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn build(self) -> ServiceProvider {
+        ServiceProvider::from_factories(self.factories)
+    }
+}
+
+/// Returned by [`ServiceCollection::add`] to collect the factory for the
+/// type passed to `add`.
+pub struct ServiceBuilder<'a, T> {
+    collection: &'a mut ServiceCollection,
+    lifetime: ServiceLifetime,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T: Any + 'static> ServiceBuilder<'a, T> {
+    pub fn with_factory<F>(self, factory: F)
+    where
+        F: Fn(&ServiceProvider) -> T + 'static,
+    {
+        self.with_fallible_factory(move |services| Ok(factory(services)))
+    }
+
+    /// Like [`ServiceBuilder::with_factory`], but lets the factory propagate
+    /// a dependency's `ResolveError` with `?` (e.g.
+    /// `services.add_trait::<dyn Foo>().with_fallible_factory(|sp| Ok(Box::new(FooImpl::new(sp.resolve()?)) as Box<dyn Foo>))`)
+    /// instead of having to `unwrap`/`expect` it away.
+    pub fn with_fallible_factory<F>(self, factory: F)
+    where
+        F: Fn(&ServiceProvider) -> Result<T, Box<dyn Error>> + 'static,
+    {
+        let boxed: Box<dyn Factory> = Box::new(factory);
+
+        self.collection
+            .factories
+            .entry(TypeId::of::<T>())
+            .or_default()
+            .push((self.lifetime, boxed));
     }
 }
 
@@ -143,6 +401,7 @@ mod test {
     use super::*;
     use std::{error::Error, rc::Rc};
 
+    #[derive(Debug)]
     struct Test1 {
         name: String,
     }
@@ -155,6 +414,7 @@ mod test {
         }
     }
 
+    #[derive(Debug)]
     struct Test2 {
         name: String,
         test1: Rc<Test1>,
@@ -186,4 +446,274 @@ mod test {
 
         Ok(())
     }
+
+    #[derive(Debug)]
+    struct Circular1 {
+        // Exists only to form the cycle; the resolve always errors out
+        // before a `Circular1` with a real `other` is ever produced.
+        #[allow(dead_code)]
+        other: Rc<Circular2>,
+    }
+
+    impl Circular1 {
+        fn factory(services: &ServiceProvider) -> Result<Circular1, Box<dyn Error>> {
+            Ok(Circular1 {
+                other: services.resolve()?,
+            })
+        }
+    }
+
+    #[derive(Debug)]
+    struct Circular2 {
+        #[allow(dead_code)]
+        other: Rc<Circular1>,
+    }
+
+    impl Circular2 {
+        fn factory(services: &ServiceProvider) -> Result<Circular2, Box<dyn Error>> {
+            Ok(Circular2 {
+                other: services.resolve()?,
+            })
+        }
+    }
+
+    #[test]
+    fn resolve_detects_circular_reference() {
+        let factories: Vec<Box<dyn Factory>> =
+            vec![Box::new(Circular1::factory), Box::new(Circular2::factory)];
+        let services = ServiceProvider::new(factories);
+
+        let error = services.resolve::<Circular1>().unwrap_err();
+
+        assert!(matches!(
+            error,
+            ResolveError {
+                kind: ResolveErrorKind::CircularReferenceFound(_),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn singleton_is_cached() {
+        let mut collection = ServiceCollection::new();
+        collection.add::<Test1>().with_factory(|_| Test1 {
+            name: String::from("Lila"),
+        });
+        let services = collection.build();
+
+        let first = services.resolve::<Test1>().unwrap();
+        let second = services.resolve::<Test1>().unwrap();
+
+        assert!(Rc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn transient_is_rebuilt_every_time() {
+        let mut collection = ServiceCollection::new();
+        collection.add_transient::<Test1>().with_factory(|_| Test1 {
+            name: String::from("Lila"),
+        });
+        let services = collection.build();
+
+        let first = services.resolve::<Test1>().unwrap();
+        let second = services.resolve::<Test1>().unwrap();
+
+        assert!(!Rc::ptr_eq(&first, &second));
+    }
+
+    trait Greeter {
+        fn greet(&self) -> String;
+    }
+
+    struct EnglishGreeter;
+
+    impl Greeter for EnglishGreeter {
+        fn greet(&self) -> String {
+            String::from("Hello")
+        }
+    }
+
+    #[test]
+    fn resolves_trait_object_by_interface() {
+        let mut collection = ServiceCollection::new();
+        collection
+            .add_trait::<dyn Greeter>()
+            .with_factory(|_| Box::new(EnglishGreeter) as Box<dyn Greeter>);
+        let services = collection.build();
+
+        let greeter = services.resolve::<Box<dyn Greeter>>().unwrap();
+
+        assert_eq!(greeter.greet(), "Hello");
+    }
+
+    #[test]
+    fn resolve_all_returns_every_registration() {
+        let mut collection = ServiceCollection::new();
+        collection.add::<Test1>().with_factory(|_| Test1 {
+            name: String::from("Lila"),
+        });
+        collection.add::<Test1>().with_factory(|_| Test1 {
+            name: String::from("Kuh"),
+        });
+        let services = collection.build();
+
+        let all = services.resolve_all::<Test1>().unwrap();
+        let names: Vec<_> = all.iter().map(|t| t.name.as_str()).collect();
+
+        assert_eq!(names, vec!["Lila", "Kuh"]);
+        assert_eq!(services.resolve::<Test1>().unwrap().name, "Kuh");
+    }
+
+    #[test]
+    fn try_resolve_returns_none_when_missing() {
+        let services = ServiceCollection::new().build();
+
+        assert!(services.try_resolve::<Test1>().is_none());
+    }
+
+    #[test]
+    fn try_resolve_returns_some_when_registered() {
+        let mut collection = ServiceCollection::new();
+        collection.add::<Test1>().with_factory(|_| Test1 {
+            name: String::from("Lila"),
+        });
+        let services = collection.build();
+
+        assert_eq!(services.try_resolve::<Test1>().unwrap().name, "Lila");
+    }
+
+    #[test]
+    fn with_fallible_factory_propagates_resolve_error_instead_of_panicking() {
+        let mut collection = ServiceCollection::new();
+        collection
+            .add::<Test2>()
+            .with_fallible_factory(|services| Test2::factory(services));
+        let services = collection.build();
+
+        let error = services.resolve::<Test2>().unwrap_err();
+
+        assert!(matches!(
+            error,
+            ResolveError {
+                kind: ResolveErrorKind::NotFound,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    #[should_panic]
+    fn try_resolve_panics_on_circular_reference() {
+        let factories: Vec<Box<dyn Factory>> =
+            vec![Box::new(Circular1::factory), Box::new(Circular2::factory)];
+        let services = ServiceProvider::new(factories);
+
+        services.try_resolve::<Circular1>();
+    }
+
+    #[test]
+    #[should_panic]
+    fn try_resolve_panics_on_circular_reference_via_service_collection() {
+        let mut collection = ServiceCollection::new();
+        collection.add::<Circular1>().with_factory(|services| {
+            Circular1 {
+                other: services.resolve().unwrap(),
+            }
+        });
+        collection.add::<Circular2>().with_factory(|services| {
+            Circular2 {
+                other: services.resolve().unwrap(),
+            }
+        });
+        let services = collection.build();
+
+        services.try_resolve::<Circular1>();
+    }
+
+    #[test]
+    fn scope_shares_singleton_with_root() {
+        let mut collection = ServiceCollection::new();
+        collection.add::<Test1>().with_factory(|_| Test1 {
+            name: String::from("Lila"),
+        });
+        let root = Rc::new(collection.build());
+
+        let from_root = root.resolve::<Test1>().unwrap();
+        let from_scope = Rc::clone(&root).create_scope().resolve::<Test1>().unwrap();
+
+        assert!(Rc::ptr_eq(&from_root, &from_scope));
+    }
+
+    #[test]
+    fn nested_scope_shares_singleton_with_root() {
+        let mut collection = ServiceCollection::new();
+        collection.add::<Test1>().with_factory(|_| Test1 {
+            name: String::from("Lila"),
+        });
+        let root = Rc::new(collection.build());
+
+        let scope1 = Rc::clone(&root).create_scope();
+        let scope2 = Rc::clone(&scope1).create_scope();
+
+        let from_root = root.resolve::<Test1>().unwrap();
+        let from_scope2 = scope2.resolve::<Test1>().unwrap();
+
+        assert!(Rc::ptr_eq(&from_root, &from_scope2));
+    }
+
+    #[test]
+    fn scope_caches_scoped_service_per_scope() {
+        let mut collection = ServiceCollection::new();
+        collection.add_scoped::<Test1>().with_factory(|_| Test1 {
+            name: String::from("Lila"),
+        });
+        let root = Rc::new(collection.build());
+
+        let scope1 = Rc::clone(&root).create_scope();
+        let scope2 = Rc::clone(&root).create_scope();
+
+        let first = scope1.resolve::<Test1>().unwrap();
+        let first_again = scope1.resolve::<Test1>().unwrap();
+        let second = scope2.resolve::<Test1>().unwrap();
+
+        assert!(Rc::ptr_eq(&first, &first_again));
+        assert!(!Rc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn lifetime_registrations_support_fallible_factories() {
+        let mut collection = ServiceCollection::new();
+        collection.add::<Test1>().with_factory(|_| Test1 {
+            name: String::from("Lila"),
+        });
+        collection
+            .add_scoped::<Test2>()
+            .with_fallible_factory(|services| Test2::factory(services));
+        let services = collection.build();
+
+        assert_eq!(services.resolve::<Test2>().unwrap().name, "Kuh");
+    }
+
+    #[test]
+    fn scope_resolve_fails_once_parent_is_dropped() {
+        let mut collection = ServiceCollection::new();
+        collection.add::<Test1>().with_factory(|_| Test1 {
+            name: String::from("Lila"),
+        });
+        let root = Rc::new(collection.build());
+
+        let scope = Rc::clone(&root).create_scope();
+        drop(root);
+
+        let error = scope.resolve::<Test1>().unwrap_err();
+
+        assert!(matches!(
+            error,
+            ResolveError {
+                kind: ResolveErrorKind::ParentDropped,
+                ..
+            }
+        ));
+    }
 }
@@ -0,0 +1,335 @@
+//! Thread-safe counterpart of the crate root, for containers shared across
+//! threads (e.g. held in a web server's application state). Mirrors
+//! [`crate::ServiceProvider`]/[`crate::ServiceCollection`], swapping
+//! `Rc`/`RefCell`/`FrozenMap` for `Arc`/`Mutex` and requiring `Send + Sync`
+//! factories and services.
+//!
+//! Every resolved service is cached on the provider and each type has one
+//! factory: registering a type again replaces its earlier factory.
+//! Lifetimes, scopes, `resolve_all`, `try_resolve` and `add_trait` have no
+//! counterpart here.
+
+use std::{
+    any::{type_name, Any, TypeId},
+    collections::HashMap,
+    error::Error,
+    fmt::Debug,
+    marker::PhantomData,
+    sync::{Arc, Mutex},
+    thread::{self, ThreadId},
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SyncResolveErrorKind {
+    #[error("Service not found!")]
+    NotFound,
+    #[error("Circular reference found: {0}!")]
+    CircularReferenceFound(String),
+    #[error("Error while resolving service!")]
+    ErrorWhileResolving(
+        #[from]
+        #[source]
+        Box<dyn Error + Send + Sync>,
+    ),
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("Could not resolve {type_name} because '{kind}'!")]
+pub struct SyncResolveError {
+    type_name: &'static str,
+    kind: SyncResolveErrorKind,
+}
+
+impl SyncResolveError {
+    fn for_type<T: 'static>() -> fn(SyncResolveErrorKind) -> SyncResolveError {
+        |kind| SyncResolveError {
+            type_name: type_name::<T>(),
+            kind,
+        }
+    }
+}
+
+/// # Safety
+///
+/// `resolve` must return an `Arc` whose underlying value's concrete type
+/// matches [`SyncFactory::type_id`]. [`SyncServiceProvider::resolve`]
+/// downcasts the result to the type it was registered under and panics if
+/// the two differ.
+pub unsafe trait SyncFactory: Send + Sync {
+    fn type_name(&self) -> &'static str;
+    fn type_id(&self) -> TypeId;
+    fn resolve(
+        &self,
+        service_provider: &SyncServiceProvider,
+    ) -> Result<Arc<dyn Any + Send + Sync>, Box<dyn Error + Send + Sync>>;
+}
+
+unsafe impl<T: Any + Send + Sync + 'static, F> SyncFactory for F
+where
+    F: Fn(&SyncServiceProvider) -> Result<T, Box<dyn Error + Send + Sync>> + Send + Sync,
+{
+    fn type_name(&self) -> &'static str {
+        type_name::<T>()
+    }
+
+    fn type_id(&self) -> TypeId {
+        TypeId::of::<T>()
+    }
+
+    fn resolve(
+        &self,
+        service_provider: &SyncServiceProvider,
+    ) -> Result<Arc<dyn Any + Send + Sync>, Box<dyn Error + Send + Sync>> {
+        let service = self(service_provider)?;
+
+        Ok(Arc::new(service))
+    }
+}
+
+impl Debug for dyn SyncFactory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("SyncFactory for {0}", self.type_name()))
+    }
+}
+
+/// Per-thread stack of `TypeId`s currently being resolved, used to detect
+/// circular dependencies. Keyed by [`ThreadId`] (rather than shared across
+/// all callers like [`crate::ServiceProvider`]'s single stack) so that two
+/// threads resolving the same type at the same time, on unrelated call
+/// chains, aren't mistaken for a cycle.
+type ResolutionStacks = Mutex<HashMap<ThreadId, (Vec<TypeId>, Vec<&'static str>)>>;
+
+/// Keeps the current thread's resolution stack balanced by popping the entry
+/// pushed for the current `resolve` call, even when that call returns early
+/// via `?`.
+struct SyncResolutionGuard<'a> {
+    resolving: &'a ResolutionStacks,
+    thread_id: ThreadId,
+}
+
+impl<'a> Drop for SyncResolutionGuard<'a> {
+    fn drop(&mut self) {
+        let mut resolving = self.resolving.lock().unwrap();
+        if let std::collections::hash_map::Entry::Occupied(mut entry) =
+            resolving.entry(self.thread_id)
+        {
+            let stack = entry.get_mut();
+            stack.0.pop();
+            stack.1.pop();
+            if stack.0.is_empty() {
+                entry.remove();
+            }
+        }
+    }
+}
+
+pub struct SyncServiceProvider {
+    factories: HashMap<TypeId, Box<dyn SyncFactory>>,
+    instances: Mutex<HashMap<TypeId, Arc<dyn Any + Send + Sync>>>,
+    resolving: ResolutionStacks,
+}
+
+impl Debug for SyncServiceProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SyncServiceProvider")
+            .field("factories", &self.factories)
+            .finish()
+    }
+}
+
+impl SyncServiceProvider {
+    pub fn resolve<T: Any + Send + Sync + 'static>(&self) -> Result<Arc<T>, SyncResolveError> {
+        let type_id = TypeId::of::<T>();
+
+        if let Some(any) = self.instances.lock().unwrap().get(&type_id).cloned() {
+            return Ok(any
+                .downcast()
+                .expect("we resolved by TypeId so it should be a T"));
+        }
+
+        let thread_id = thread::current().id();
+        {
+            let mut resolving = self.resolving.lock().unwrap();
+            let stack = resolving.entry(thread_id).or_default();
+
+            if stack.0.contains(&type_id) {
+                let mut chain = stack.1.clone();
+                chain.push(type_name::<T>());
+
+                return Err(SyncResolveErrorKind::CircularReferenceFound(
+                    chain.join(" -> "),
+                ))
+                .map_err(SyncResolveError::for_type::<T>());
+            }
+
+            stack.0.push(type_id);
+            stack.1.push(type_name::<T>());
+        }
+        let _guard = SyncResolutionGuard {
+            resolving: &self.resolving,
+            thread_id,
+        };
+
+        let factory = self
+            .factories
+            .get(&type_id)
+            .ok_or(SyncResolveErrorKind::NotFound)
+            .map_err(SyncResolveError::for_type::<T>())?;
+
+        let service = factory.resolve(self).map_err(|err| {
+            // A nested `resolve` call boxes its `SyncResolveError` like any
+            // other error; unwrap it instead of re-wrapping it in
+            // `ErrorWhileResolving` so callers still see the original
+            // `NotFound`/`CircularReferenceFound`.
+            match err.downcast::<SyncResolveError>() {
+                Ok(resolve_error) => *resolve_error,
+                Err(err) => SyncResolveError::for_type::<T>()(
+                    SyncResolveErrorKind::ErrorWhileResolving(err),
+                ),
+            }
+        })?;
+
+        self.instances
+            .lock()
+            .unwrap()
+            .insert(type_id, service.clone());
+
+        Ok(service
+            .downcast()
+            .expect("We just resolved the factory for T"))
+    }
+}
+
+/// Builds up the set of registered services before handing them to a
+/// [`SyncServiceProvider`] via [`SyncServiceCollection::build`].
+#[derive(Default)]
+pub struct SyncServiceCollection {
+    factories: HashMap<TypeId, Box<dyn SyncFactory>>,
+}
+
+impl SyncServiceCollection {
+    pub fn new() -> Self {
+        Self {
+            factories: HashMap::new(),
+        }
+    }
+
+    pub fn add<T: Any + Send + Sync + 'static>(&mut self) -> SyncServiceBuilder<'_, T> {
+        SyncServiceBuilder {
+            collection: self,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn build(self) -> SyncServiceProvider {
+        SyncServiceProvider {
+            factories: self.factories,
+            instances: Mutex::new(HashMap::new()),
+            resolving: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+/// Returned by [`SyncServiceCollection::add`] to collect the factory for the
+/// type passed to `add`.
+pub struct SyncServiceBuilder<'a, T> {
+    collection: &'a mut SyncServiceCollection,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T: Any + Send + Sync + 'static> SyncServiceBuilder<'a, T> {
+    pub fn with_factory<F>(self, factory: F)
+    where
+        F: Fn(&SyncServiceProvider) -> T + Send + Sync + 'static,
+    {
+        self.with_fallible_factory(move |services| Ok(factory(services)))
+    }
+
+    /// Like [`SyncServiceBuilder::with_factory`], but lets the factory
+    /// propagate a dependency's `SyncResolveError` with `?` instead of
+    /// having to `unwrap`/`expect` it away.
+    pub fn with_fallible_factory<F>(self, factory: F)
+    where
+        F: Fn(&SyncServiceProvider) -> Result<T, Box<dyn Error + Send + Sync>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        let boxed: Box<dyn SyncFactory> = Box::new(factory);
+
+        self.collection.factories.insert(TypeId::of::<T>(), boxed);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Test1 {
+        name: String,
+    }
+
+    #[derive(Debug)]
+    struct Circular1 {
+        other: Arc<Circular2>,
+    }
+
+    #[derive(Debug)]
+    struct Circular2 {
+        other: Arc<Circular1>,
+    }
+
+    #[test]
+    fn resolve_detects_circular_reference() {
+        let mut collection = SyncServiceCollection::new();
+        collection
+            .add::<Circular1>()
+            .with_fallible_factory(|services| {
+                Ok(Circular1 {
+                    other: services.resolve()?,
+                })
+            });
+        collection
+            .add::<Circular2>()
+            .with_fallible_factory(|services| {
+                Ok(Circular2 {
+                    other: services.resolve()?,
+                })
+            });
+        let services = collection.build();
+
+        let error = services.resolve::<Circular1>().unwrap_err();
+
+        assert!(matches!(
+            error,
+            SyncResolveError {
+                kind: SyncResolveErrorKind::CircularReferenceFound(_),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn resolve_is_cached_and_thread_safe() {
+        let mut collection = SyncServiceCollection::new();
+        collection.add::<Test1>().with_factory(|_| Test1 {
+            name: String::from("Lila"),
+        });
+        let services = Arc::new(collection.build());
+
+        let first = services.resolve::<Test1>().unwrap();
+        let second = services.resolve::<Test1>().unwrap();
+
+        assert!(Arc::ptr_eq(&first, &second));
+
+        let services_in_thread = Arc::clone(&services);
+        let name = std::thread::spawn(move || {
+            services_in_thread.resolve::<Test1>().unwrap().name.clone()
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(name, "Lila");
+    }
+}